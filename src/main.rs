@@ -1,19 +1,27 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    response::sse::{Event, KeepAlive, Sse},
     response::Html,
-    routing::get,
+    http::StatusCode,
+    routing::{get, post},
     Json, Router,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use regex::Regex;
 use sysinfo::{System, Disks, Components, Networks};
 use bollard::Docker;
-use bollard::container::{ListContainersOptions, LogsOptions};
+use bollard::container::{ListContainersOptions, LogsOptions, StatsOptions};
 use bollard::image::ListImagesOptions;
-use futures_util::StreamExt;
+use futures_util::{Stream, StreamExt};
 use std::sync::{Arc, Mutex};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
 use chrono::{Duration, Utc};
 use jemallocator::Jemalloc;
+use metrics::gauge;
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use metrics_util::MetricKindMask;
 
 #[global_allocator]
 static GLOBAL: Jemalloc = Jemalloc;
@@ -22,6 +30,360 @@ struct AppState {
     sys: Mutex<System>,
     networks: Mutex<Networks>,
     docker: Docker,
+    prometheus: PrometheusHandle,
+    history: Mutex<History>,
+    sample_tx: broadcast::Sender<Sample>,
+    config: Config,
+    thresholds: Thresholds,
+    /// Most recent alert evaluation, refreshed by the sampler so `/api/alerts`
+    /// and `/healthz` can answer from cache instead of re-running the expensive
+    /// `collect_status` (latency probe + per-container stats) on every hit.
+    alerts: Mutex<Vec<Alert>>,
+}
+
+/// Warn/critical limits used to raise alerts, read from the environment.
+///
+/// Each field is a `(warn, critical)` pair; a measured value at or above the
+/// warn level raises a `warn` alert, at or above the critical level a
+/// `critical` one.
+#[derive(Clone)]
+struct Thresholds {
+    disk_used_pct: (f64, f64),
+    ram_used_pct: (f64, f64),
+    swap_used_pct: (f64, f64),
+    load1: (f64, f64),
+    latency_ms: (f64, f64),
+}
+
+impl Thresholds {
+    fn from_env() -> Self {
+        fn pair(prefix: &str, def_warn: f64, def_crit: f64) -> (f64, f64) {
+            let warn = std::env::var(format!("ALERT_{}_WARN", prefix))
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(def_warn);
+            let crit = std::env::var(format!("ALERT_{}_CRIT", prefix))
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(def_crit);
+            (warn, crit)
+        }
+        Thresholds {
+            disk_used_pct: pair("DISK_PCT", 80.0, 95.0),
+            ram_used_pct: pair("RAM_PCT", 85.0, 95.0),
+            swap_used_pct: pair("SWAP_PCT", 50.0, 90.0),
+            load1: pair("LOAD1", 4.0, 8.0),
+            latency_ms: pair("LATENCY_MS", 100.0, 500.0),
+        }
+    }
+}
+
+/// A single active alert: one metric that has crossed a configured limit.
+#[derive(Clone, Serialize)]
+struct Alert {
+    severity: &'static str,
+    metric: String,
+    value: f64,
+    threshold: f64,
+}
+
+/// Evaluate the current status against the configured thresholds, returning
+/// every metric that is at or over its warn/critical limit, plus a `warn`
+/// alert for `internet_latency_ms` when the latency probe failed outright.
+fn evaluate_alerts(status: &FullStatus, t: &Thresholds) -> Vec<Alert> {
+    fn check(out: &mut Vec<Alert>, metric: String, value: f64, (warn, crit): (f64, f64)) {
+        if value >= crit {
+            out.push(Alert { severity: "critical", metric, value, threshold: crit });
+        } else if value >= warn {
+            out.push(Alert { severity: "warn", metric, value, threshold: warn });
+        }
+    }
+
+    let mut alerts = Vec::new();
+
+    if status.ram_total_mb > 0 {
+        let pct = status.ram_used_mb as f64 / status.ram_total_mb as f64 * 100.0;
+        check(&mut alerts, "ram_used_pct".to_string(), pct, t.ram_used_pct);
+    }
+    if status.swap_total_mb > 0 {
+        let pct = status.swap_used_mb as f64 / status.swap_total_mb as f64 * 100.0;
+        check(&mut alerts, "swap_used_pct".to_string(), pct, t.swap_used_pct);
+    }
+    for d in &status.disks {
+        if d.total_gb > 0 {
+            let pct = d.used_gb as f64 / d.total_gb as f64 * 100.0;
+            check(&mut alerts, format!("disk_used_pct:{}", d.mount_point), pct, t.disk_used_pct);
+        }
+    }
+    check(&mut alerts, "load1".to_string(), status.load_avg[0], t.load1);
+    // A non-positive latency means the probe couldn't reach the internet at
+    // all (`measure_latency` returns 0.0 on failure); surface it as an alert so
+    // an outage isn't read as a healthy zero, but only `warn` — host
+    // internet-reachability is decoupled from this process's liveness, so it
+    // must not drive `/healthz` to 503 and get the container restarted.
+    if status.internet_latency_ms <= 0.0 {
+        alerts.push(Alert {
+            severity: "warn",
+            metric: "internet_latency_ms".to_string(),
+            value: status.internet_latency_ms,
+            threshold: t.latency_ms.1,
+        });
+    } else {
+        check(&mut alerts, "internet_latency_ms".to_string(), status.internet_latency_ms, t.latency_ms);
+    }
+
+    alerts
+}
+
+/// Runtime knobs for the background sampler, read from the environment.
+#[derive(Clone)]
+struct Config {
+    /// How often the sampler collects a new data point, in seconds.
+    sample_interval_secs: u64,
+    /// Maximum number of points kept per series before the oldest is dropped.
+    history_len: usize,
+}
+
+impl Config {
+    fn from_env() -> Self {
+        let sample_interval_secs = std::env::var("SAMPLE_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(5);
+        let history_len = std::env::var("HISTORY_LEN")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(240);
+        Config { sample_interval_secs, history_len }
+    }
+}
+
+/// A single collected data point, broadcast to SSE subscribers as it lands.
+#[derive(Clone, Serialize)]
+struct Sample {
+    timestamp: f64,
+    metric: String,
+    value: f64,
+}
+
+/// Fixed-size per-metric ring buffers, keyed by metric/container name.
+///
+/// Each series keeps at most `cap` `(timestamp, value)` points — the same
+/// `Vec<(f64, f64)>` chart-dataset shape oxker feeds its CPU/mem charts.
+struct History {
+    series: HashMap<String, VecDeque<(f64, f64)>>,
+    cap: usize,
+}
+
+impl History {
+    fn new(cap: usize) -> Self {
+        History { series: HashMap::new(), cap }
+    }
+
+    fn push(&mut self, metric: &str, point: (f64, f64)) {
+        if self.cap == 0 {
+            return;
+        }
+        let buf = self
+            .series
+            .entry(metric.to_string())
+            .or_insert_with(|| VecDeque::with_capacity(self.cap));
+        if buf.len() == self.cap {
+            buf.pop_front();
+        }
+        buf.push_back(point);
+    }
+
+    /// Drop every series whose key fails `keep`, so per-container keys for
+    /// containers that have gone away don't accumulate forever.
+    fn retain<F: Fn(&str) -> bool>(&mut self, keep: F) {
+        self.series.retain(|k, _| keep(k));
+    }
+}
+
+/// One-shot resource usage for a single container, from bollard's stats API.
+#[derive(Clone)]
+struct ContainerStats {
+    cpu_percent: f64,
+    mem_used_mb: f64,
+    mem_limit_mb: f64,
+    net_rx_bytes: u64,
+    net_tx_bytes: u64,
+}
+
+/// Fetch a single (non-streaming) stats sample for `name`.
+///
+/// CPU percent is derived from the delta between `cpu_stats` and
+/// `precpu_stats`; memory usage excludes the page cache where the daemon
+/// reports it. Returns `None` if the container has no stats (e.g. stopped).
+async fn fetch_container_stats(docker: &Docker, name: &str) -> Option<ContainerStats> {
+    // `one_shot` is deliberately false: Docker then streams two cycles back to
+    // back, so `precpu_stats` carries a real previous reading and the CPU
+    // delta formula reflects *current* usage rather than the lifetime average.
+    let options = Some(StatsOptions { stream: false, one_shot: false });
+    let stats = docker.stats(name, options).next().await?.ok()?;
+
+    let cpu_delta = stats.cpu_stats.cpu_usage.total_usage as f64
+        - stats.precpu_stats.cpu_usage.total_usage as f64;
+    let system_delta = stats.cpu_stats.system_cpu_usage.unwrap_or(0) as f64
+        - stats.precpu_stats.system_cpu_usage.unwrap_or(0) as f64;
+    let online_cpus = stats.cpu_stats.online_cpus.unwrap_or(1) as f64;
+    let cpu_percent = if system_delta > 0.0 {
+        (cpu_delta / system_delta) * online_cpus * 100.0
+    } else {
+        0.0
+    };
+
+    let cache = match &stats.memory_stats.stats {
+        Some(bollard::container::MemoryStatsStats::V1(v1)) => v1.cache,
+        _ => 0,
+    };
+    let mem_used = stats.memory_stats.usage.unwrap_or(0).saturating_sub(cache);
+    let mem_limit = stats.memory_stats.limit.unwrap_or(0);
+
+    let (rx, tx) = stats
+        .networks
+        .map(|nets| {
+            nets.values()
+                .fold((0u64, 0u64), |(rx, tx), n| (rx + n.rx_bytes, tx + n.tx_bytes))
+        })
+        .unwrap_or((0, 0));
+
+    Some(ContainerStats {
+        cpu_percent,
+        mem_used_mb: mem_used as f64 / 1024.0 / 1024.0,
+        mem_limit_mb: mem_limit as f64 / 1024.0 / 1024.0,
+        net_rx_bytes: rx,
+        net_tx_bytes: tx,
+    })
+}
+
+/// Background task: every `sample_interval_secs`, collect a fresh set of data
+/// points, append them to the history ring buffers and broadcast each one to
+/// any live `/api/stream` subscribers.
+async fn sampler(state: Arc<AppState>) {
+    // The sampler owns its own `System`/`Networks` so its deltas are measured
+    // strictly tick-to-tick. Sharing `state.sys`/`state.networks` with the
+    // request handlers would let a concurrent status poll refresh the counters
+    // between ticks, shrinking the interval and corrupting the history series.
+    let mut sys = System::new();
+    let mut networks = Networks::new_with_refreshed_list();
+    let mut ticker =
+        tokio::time::interval(std::time::Duration::from_secs(state.config.sample_interval_secs));
+    // If a tick runs long, skip the missed slots rather than firing bunched
+    // catch-up ticks, which would stamp several samples with near-equal times.
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        ticker.tick().await;
+        let ts = Utc::now().timestamp() as f64;
+
+        sys.refresh_cpu();
+        sys.refresh_memory();
+        let cpu = sys.global_cpu_info().cpu_usage() as f64;
+        let ram_used = sys.used_memory() / 1024 / 1024;
+        let ram_total = sys.total_memory() / 1024 / 1024;
+        let swap_used = sys.used_swap() / 1024 / 1024;
+        let swap_total = sys.total_swap() / 1024 / 1024;
+        let load = System::load_average();
+
+        // `received`/`transmitted` are byte counts since the last refresh,
+        // i.e. already the per-interval deltas we want to chart.
+        networks.refresh();
+        let net = networks
+            .iter()
+            .map(|(n, d)| (n.clone(), d.received() as f64, d.transmitted() as f64))
+            .collect::<Vec<_>>();
+
+        let mut samples: Vec<(String, f64)> = vec![
+            ("cpu".to_string(), cpu),
+            ("ram_used".to_string(), ram_used as f64),
+            ("swap_used".to_string(), swap_used as f64),
+        ];
+        for (name, rx, tx) in net {
+            samples.push((format!("net_rx:{}", name), rx));
+            samples.push((format!("net_tx:{}", name), tx));
+        }
+
+        // Per-container stats for everything currently running, fetched
+        // concurrently so one slow container can't stall the tick.
+        let mut live_containers = HashSet::new();
+        if let Ok(list) = state
+            .docker
+            .list_containers(Some(ListContainersOptions::<String> { all: false, ..Default::default() }))
+            .await
+        {
+            let names: Vec<String> = list
+                .into_iter()
+                .filter_map(|c| c.names.map(|n| n.join("").replace('/', "")))
+                .collect();
+            let results = futures_util::future::join_all(
+                names.iter().map(|n| fetch_container_stats(&state.docker, n)),
+            )
+            .await;
+            for (name, stats) in names.iter().zip(results) {
+                live_containers.insert(name.clone());
+                if let Some(s) = stats {
+                    samples.push((format!("container_cpu:{}", name), s.cpu_percent));
+                    samples.push((format!("container_mem:{}", name), s.mem_used_mb));
+                }
+            }
+        }
+
+        // Refresh the cached alert evaluation once per tick so the health
+        // endpoints stay cheap. This reuses the figures already gathered above
+        // plus a disk refresh and a single latency probe — no second container
+        // list / stats sweep. Collection fields the alert rules don't consult
+        // (networks, processes, sensors, containers, images) are left empty.
+        let disks = Disks::new_with_refreshed_list()
+            .iter()
+            .map(|d| {
+                let total = d.total_space();
+                let available = d.available_space();
+                DiskInfo {
+                    name: d.name().to_string_lossy().into_owned(),
+                    mount_point: d.mount_point().to_string_lossy().into_owned(),
+                    total_gb: total / 1024 / 1024 / 1024,
+                    used_gb: (total - available) / 1024 / 1024 / 1024,
+                }
+            })
+            .collect::<Vec<_>>();
+        let alert_view = FullStatus {
+            cpu_usage: cpu as f32,
+            ram_used_mb: ram_used,
+            ram_total_mb: ram_total,
+            swap_used_mb: swap_used,
+            swap_total_mb: swap_total,
+            uptime_secs: System::uptime(),
+            load_avg: [load.one, load.five, load.fifteen],
+            internet_latency_ms: measure_latency().await,
+            networks: Vec::new(),
+            processes: Vec::new(),
+            sensors: Vec::new(),
+            disks,
+            containers: Vec::new(),
+            images: Vec::new(),
+        };
+        *state.alerts.lock().unwrap() = evaluate_alerts(&alert_view, &state.thresholds);
+
+        // Sweep idle Prometheus gauges so series for entities that stopped
+        // being updated (removed container/disk/iface/sensor) actually expire;
+        // the configured `idle_timeout` only takes effect when upkeep runs, and
+        // `render()` never triggers it on its own.
+        state.prometheus.run_upkeep();
+
+        let mut hist = state.history.lock().unwrap();
+        // Evict per-container series for containers that are no longer running,
+        // so the key set can't grow without bound as containers churn.
+        hist.retain(|k| match k.split_once(':') {
+            Some(("container_cpu" | "container_mem", name)) => live_containers.contains(name),
+            _ => true,
+        });
+        for (metric, value) in samples {
+            hist.push(&metric, (ts, value));
+            // A send error just means nobody is listening right now.
+            let _ = state.sample_tx.send(Sample { timestamp: ts, metric, value });
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -38,6 +400,44 @@ struct ContainerInfo {
     status: String,
     state: String,
     ports: String,
+    // Lifecycle actions that are legal for this container's current state,
+    // so the UI only renders buttons it's allowed to press.
+    actions: Vec<&'static str>,
+    // Live resource usage, populated for running containers from the
+    // Docker stats API; zero for anything not running.
+    cpu_percent: f64,
+    mem_used_mb: f64,
+    mem_limit_mb: f64,
+    net_rx_bytes: u64,
+    net_tx_bytes: u64,
+}
+
+/// Lifecycle actions the dashboard can drive against a container.
+///
+/// Which ones are offered depends on the container's reported `State`
+/// (see `actions_for_state`), mirroring oxker's `DockerControls::gen_vec`.
+const ALL_ACTIONS: &[&str] = &["start", "stop", "restart", "pause", "unpause"];
+
+/// Return the subset of lifecycle actions that make sense for `state`.
+///
+/// A running container can be stopped, paused or restarted; a paused one
+/// can be unpaused or stopped; anything stopped/dead only offers start or
+/// restart.
+fn actions_for_state(state: &str) -> Vec<&'static str> {
+    match state {
+        "running" => vec!["stop", "pause", "restart"],
+        "paused" => vec!["unpause", "stop"],
+        "exited" | "dead" | "created" => vec!["start", "restart"],
+        _ => vec!["start", "stop", "restart"],
+    }
+}
+
+#[derive(Serialize)]
+struct ActionResult {
+    name: String,
+    action: String,
+    success: bool,
+    error: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -93,16 +493,31 @@ async fn measure_latency() -> f64 {
     0.0
 }
 
-fn get_top_ram_processes() -> Vec<ProcessInfo> {
+/// Query parameters for `/api/processes`.
+#[derive(Deserialize)]
+struct ProcessQuery {
+    /// Match against the process `comm` name; empty/absent matches everything.
+    filter: Option<String>,
+    /// Maximum number of processes to return (defaults to all matches).
+    limit: Option<usize>,
+    /// When true, treat `filter` as a regular expression instead of a
+    /// case-insensitive substring.
+    #[serde(default)]
+    regex: bool,
+}
+
+/// Scan `/proc` for resident-memory usage, returning every process whose RSS
+/// is at least `min_mb`, sorted by RAM descending.
+fn scan_processes(min_mb: u64) -> Vec<ProcessInfo> {
     let mut procs = Vec::new();
     let page_size = 4096; // Standard 4KB page size
-    
+
     if let Ok(entries) = std::fs::read_dir("/proc") {
         for entry in entries.flatten() {
             if let Ok(meta) = entry.metadata() {
                 if !meta.is_dir() { continue; }
             }
-            
+
             let name = entry.file_name();
             let pid_str = name.to_string_lossy();
             if !pid_str.chars().all(char::is_numeric) { continue; }
@@ -116,7 +531,7 @@ fn get_top_ram_processes() -> Vec<ProcessInfo> {
                     if let Ok(resident_pages) = resident_str.parse::<u64>() {
                         let ram_mb = (resident_pages * page_size) / 1024 / 1024;
                         // Avoid reading files for tiny processes to save IO
-                        if ram_mb > 10 { 
+                        if ram_mb >= min_mb {
                             let mut name = "unknown".to_string();
                             if let Ok(comm) = std::fs::read_to_string(path.join("comm")) {
                                 name = comm.trim().to_string();
@@ -128,14 +543,48 @@ fn get_top_ram_processes() -> Vec<ProcessInfo> {
             }
         }
     }
-    
-    // Sort desc and take top 3
+
     procs.sort_by(|a, b| b.ram_mb.cmp(&a.ram_mb));
+    procs
+}
+
+fn get_top_ram_processes() -> Vec<ProcessInfo> {
+    // Default dashboard view: heaviest three processes over 10 MB.
+    let mut procs = scan_processes(10);
     procs.truncate(3);
     procs
 }
 
+async fn get_processes(Query(q): Query<ProcessQuery>) -> Json<Vec<ProcessInfo>> {
+    let mut procs = tokio::task::spawn_blocking(|| scan_processes(0))
+        .await
+        .unwrap_or_default();
+
+    if let Some(pattern) = q.filter.as_deref().filter(|p| !p.is_empty()) {
+        if q.regex {
+            // On an invalid pattern, keep the full set rather than erroring —
+            // the same way bottom holds a base pattern for an empty query.
+            if let Ok(re) = Regex::new(pattern) {
+                procs.retain(|p| re.is_match(&p.name));
+            }
+        } else {
+            let needle = pattern.to_lowercase();
+            procs.retain(|p| p.name.to_lowercase().contains(&needle));
+        }
+    }
+
+    if let Some(limit) = q.limit {
+        procs.truncate(limit);
+    }
+
+    Json(procs)
+}
+
 async fn get_full_status(State(state): State<Arc<AppState>>) -> Json<FullStatus> {
+    Json(collect_status(&state).await)
+}
+
+async fn collect_status(state: &Arc<AppState>) -> FullStatus {
     // 1. Hardware Stats
     let (cpu_usage, ram_used, ram_total, swap_used, swap_total, uptime, load_avg, sensors, disks) = {
         let mut sys = state.sys.lock().unwrap();
@@ -204,15 +653,48 @@ async fn get_full_status(State(state): State<Arc<AppState>>) -> Json<FullStatus>
                 p.public_port.map(|pub_p| format!("{}:{}", pub_p, p.private_port))
             }).collect::<Vec<_>>().join(", ");
 
+            let state = c.state.unwrap_or_default();
+            let actions = actions_for_state(&state);
             containers.push(ContainerInfo {
                 name: c.names.unwrap_or_default().join("").replace("/", ""),
                 status: c.status.unwrap_or_default(),
-                state: c.state.unwrap_or_default(),
+                state,
                 ports: if port_info.is_empty() { "-".to_string() } else { port_info },
+                actions,
+                cpu_percent: 0.0,
+                mem_used_mb: 0.0,
+                mem_limit_mb: 0.0,
+                net_rx_bytes: 0,
+                net_tx_bytes: 0,
             });
         }
     }
 
+    // Pull live stats for every running container concurrently so a busy
+    // host with many containers doesn't serialise one stats call per request.
+    let running: Vec<usize> = containers
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.state == "running")
+        .map(|(i, _)| i)
+        .collect();
+    let stats = futures_util::future::join_all(
+        running
+            .iter()
+            .map(|&i| fetch_container_stats(&state.docker, &containers[i].name)),
+    )
+    .await;
+    for (&i, stat) in running.iter().zip(stats) {
+        if let Some(s) = stat {
+            let c = &mut containers[i];
+            c.cpu_percent = s.cpu_percent;
+            c.mem_used_mb = s.mem_used_mb;
+            c.mem_limit_mb = s.mem_limit_mb;
+            c.net_rx_bytes = s.net_rx_bytes;
+            c.net_tx_bytes = s.net_tx_bytes;
+        }
+    }
+
     // 5. Docker Images
     let mut images = Vec::new();
     if let Ok(list) = state.docker.list_images(Some(ListImagesOptions::<String> { all: true, ..Default::default() })).await {
@@ -229,10 +711,10 @@ async fn get_full_status(State(state): State<Arc<AppState>>) -> Json<FullStatus>
         }
     }
 
-    Json(FullStatus { 
-        cpu_usage, 
-        ram_used_mb: ram_used, 
-        ram_total_mb: ram_total, 
+    FullStatus {
+        cpu_usage,
+        ram_used_mb: ram_used,
+        ram_total_mb: ram_total,
         swap_used_mb: swap_used,
         swap_total_mb: swap_total,
         uptime_secs: uptime,
@@ -240,11 +722,11 @@ async fn get_full_status(State(state): State<Arc<AppState>>) -> Json<FullStatus>
         internet_latency_ms: latency,
         networks: networks_list,
         processes,
-        sensors, 
-        disks, 
-        containers, 
-        images 
-    })
+        sensors,
+        disks,
+        containers,
+        images
+    }
 }
 
 async fn get_container_logs(Path(name): Path<String>, State(state): State<Arc<AppState>>) -> String {
@@ -261,6 +743,111 @@ async fn get_container_logs(Path(name): Path<String>, State(state): State<Arc<Ap
     if output.is_empty() { "No logs in last 30m.".to_string() } else { output }
 }
 
+async fn get_alerts(State(state): State<Arc<AppState>>) -> Json<Vec<Alert>> {
+    Json(state.alerts.lock().unwrap().clone())
+}
+
+async fn healthz(State(state): State<Arc<AppState>>) -> StatusCode {
+    let critical = state
+        .alerts
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|a| a.severity == "critical");
+    if critical {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    }
+}
+
+async fn get_history(
+    State(state): State<Arc<AppState>>,
+) -> Json<HashMap<String, Vec<(f64, f64)>>> {
+    let hist = state.history.lock().unwrap();
+    let out = hist
+        .series
+        .iter()
+        .map(|(k, v)| (k.clone(), v.iter().cloned().collect()))
+        .collect();
+    Json(out)
+}
+
+async fn get_stream(
+    State(state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>> {
+    let stream = BroadcastStream::new(state.sample_tx.subscribe()).filter_map(|res| async move {
+        // Drop lagged/errored frames rather than tearing down the stream.
+        res.ok()
+            .and_then(|sample| Event::default().json_data(&sample).ok())
+            .map(Ok)
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn get_metrics(State(state): State<Arc<AppState>>) -> String {
+    let status = collect_status(&state).await;
+
+    gauge!("node_cpu_usage_percent").set(status.cpu_usage as f64);
+    gauge!("node_memory_used_bytes").set((status.ram_used_mb * 1024 * 1024) as f64);
+    gauge!("node_memory_total_bytes").set((status.ram_total_mb * 1024 * 1024) as f64);
+    gauge!("node_swap_used_bytes").set((status.swap_used_mb * 1024 * 1024) as f64);
+    gauge!("node_swap_total_bytes").set((status.swap_total_mb * 1024 * 1024) as f64);
+    gauge!("node_uptime_seconds").set(status.uptime_secs as f64);
+    gauge!("node_load1").set(status.load_avg[0]);
+    gauge!("node_load5").set(status.load_avg[1]);
+    gauge!("node_load15").set(status.load_avg[2]);
+    gauge!("node_internet_latency_ms").set(status.internet_latency_ms);
+
+    for d in &status.disks {
+        gauge!("node_disk_total_bytes", "mount" => d.mount_point.clone())
+            .set((d.total_gb * 1024 * 1024 * 1024) as f64);
+        gauge!("node_disk_used_bytes", "mount" => d.mount_point.clone())
+            .set((d.used_gb * 1024 * 1024 * 1024) as f64);
+    }
+    for n in &status.networks {
+        gauge!("node_network_receive_bytes", "iface" => n.name.clone()).set(n.rx_bytes as f64);
+        gauge!("node_network_transmit_bytes", "iface" => n.name.clone()).set(n.tx_bytes as f64);
+    }
+    for (label, temp) in &status.sensors {
+        gauge!("node_hwmon_temp_celsius", "sensor" => label.clone()).set(*temp as f64);
+    }
+    for c in &status.containers {
+        let up = if c.state == "running" { 1.0 } else { 0.0 };
+        // `container_up` keys on name only, so a running→exited transition
+        // toggles the same series 1→0 instead of leaving a stale `state`
+        // label stuck at 1. The `state` string lives on a separate info gauge.
+        gauge!("container_up", "name" => c.name.clone()).set(up);
+        gauge!("container_info", "name" => c.name.clone(), "state" => c.state.clone()).set(1.0);
+    }
+
+    state.prometheus.render()
+}
+
+async fn container_action(
+    Path((name, action)): Path<(String, String)>,
+    State(state): State<Arc<AppState>>,
+) -> Json<ActionResult> {
+    let result: Result<(), bollard::errors::Error> = match action.as_str() {
+        "start" => state.docker.start_container::<String>(&name, None).await,
+        "stop" => state.docker.stop_container(&name, None).await,
+        "restart" => state.docker.restart_container(&name, None).await,
+        "pause" => state.docker.pause_container(&name).await,
+        "unpause" => state.docker.unpause_container(&name).await,
+        other => Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 400,
+            message: format!("unknown action '{}', expected one of {:?}", other, ALL_ACTIONS),
+        }),
+    };
+
+    let (success, error) = match result {
+        Ok(()) => (true, None),
+        Err(e) => (false, Some(e.to_string())),
+    };
+
+    Json(ActionResult { name, action, success, error })
+}
+
 async fn ui_handler() -> Html<&'static str> {
     Html(include_str!("index.html"))
 }
@@ -268,16 +855,43 @@ async fn ui_handler() -> Html<&'static str> {
 #[tokio::main]
 async fn main() {
     let networks = Networks::new_with_refreshed_list();
+    // Expire gauges that stop being updated (a removed container, unmounted
+    // disk, vanished interface/sensor) so scrapers don't keep reading the last
+    // value of something that no longer exists.
+    let prometheus = PrometheusBuilder::new()
+        .idle_timeout(
+            MetricKindMask::GAUGE,
+            Some(std::time::Duration::from_secs(60)),
+        )
+        .install_recorder()
+        .expect("failed to install Prometheus recorder");
+    let config = Config::from_env();
+    let (sample_tx, _) = broadcast::channel(256);
     let shared_state = Arc::new(AppState {
         sys: Mutex::new(System::new()),
         networks: Mutex::new(networks),
         docker: Docker::connect_with_unix_defaults().expect("Docker socket error"),
+        prometheus,
+        history: Mutex::new(History::new(config.history_len)),
+        sample_tx,
+        config,
+        thresholds: Thresholds::from_env(),
+        alerts: Mutex::new(Vec::new()),
     });
 
+    tokio::spawn(sampler(shared_state.clone()));
+
     let app = Router::new()
         .route("/", get(ui_handler))
         .route("/api/status", get(get_full_status))
         .route("/api/logs/:name", get(get_container_logs))
+        .route("/api/container/:name/:action", post(container_action))
+        .route("/metrics", get(get_metrics))
+        .route("/api/processes", get(get_processes))
+        .route("/api/history", get(get_history))
+        .route("/api/stream", get(get_stream))
+        .route("/api/alerts", get(get_alerts))
+        .route("/healthz", get(healthz))
         .with_state(shared_state);
 
     let addr = "0.0.0.0:9996";